@@ -0,0 +1,69 @@
+//! Command-line surface for the binary: a `clap`-derived command so the same
+//! binary can launch the desktop GUI, crawl-and-index headlessly (for
+//! cron/CI), or answer a single query and exit, instead of always opening a
+//! window.
+
+use clap::{Parser, Subcommand};
+
+#[derive(Debug, Parser)]
+#[command(name = "some-indexed-document-searcher", about = "Index and search a corpus of files")]
+pub struct MainCommand {
+    /// Tracing log level, e.g. "trace", "debug", "info", "warn", "error".
+    #[arg(long, default_value = "info")]
+    pub log_level: String,
+
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+impl MainCommand {
+    /// The subcommand to run, falling back to `Command::default()` (the GUI)
+    /// when none was given on the command line.
+    pub fn command(self) -> Command {
+        self.command.unwrap_or_default()
+    }
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Launch the desktop search GUI (default behavior).
+    Gui {
+        /// Also serve the index over HTTP alongside the GUI.
+        #[arg(long)]
+        serve: bool,
+
+        /// Override the configured HTTP bind address.
+        #[arg(long)]
+        bind: Option<String>,
+    },
+
+    /// Crawl the configured directories, index them, and exit.
+    Index,
+
+    /// Run a single query against the existing index and print ranked hits.
+    Search {
+        query: String,
+
+        #[arg(long, default_value_t = 10)]
+        limit: usize,
+
+        #[arg(long, default_value_t = 0)]
+        offset: usize,
+    },
+
+    /// Serve the existing index over HTTP, read-only, without a GUI.
+    Serve {
+        /// Override the configured HTTP bind address.
+        #[arg(long)]
+        bind: Option<String>,
+    },
+}
+
+impl Default for Command {
+    fn default() -> Self {
+        Command::Gui {
+            serve: false,
+            bind: None,
+        }
+    }
+}
@@ -0,0 +1,71 @@
+use serde::Deserialize;
+use snafu::{ResultExt, Snafu};
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Snafu)]
+pub enum Error {
+    #[snafu(display("could not read config file {}: {}", path.display(), source))]
+    Read {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    #[snafu(display("could not parse config file {}: {}", path.display(), source))]
+    Parse {
+        path: PathBuf,
+        source: toml::de::Error,
+    },
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct Config {
+    /// Directories that are crawled for files to index.
+    pub watch_dirs: Vec<PathBuf>,
+
+    /// Directory the tantivy index is persisted to.
+    pub index_dir: PathBuf,
+
+    /// Path to the last-modified-time cache, used to skip unchanged files.
+    pub cache_path: PathBuf,
+
+    /// Number of worker threads used to apply `IndexRequest`s to the index.
+    #[serde(default = "default_indexer_threads")]
+    pub indexer_threads: usize,
+
+    /// Where to write (and restore from) periodic snapshots. If unset,
+    /// snapshotting is disabled.
+    #[serde(default)]
+    pub snapshot_path: Option<PathBuf>,
+
+    /// How often, in seconds, to take a snapshot. Ignored if
+    /// `snapshot_path` is unset.
+    #[serde(default = "default_snapshot_interval_secs")]
+    pub snapshot_interval_secs: u64,
+
+    /// Address the HTTP search server binds to when enabled, either via the
+    /// `serve` subcommand or by running the GUI with serving turned on.
+    #[serde(default = "default_serve_bind_addr")]
+    pub serve_bind_addr: String,
+}
+
+fn default_indexer_threads() -> usize {
+    2
+}
+
+fn default_snapshot_interval_secs() -> u64 {
+    60 * 30
+}
+
+fn default_serve_bind_addr() -> String {
+    "127.0.0.1:8080".to_string()
+}
+
+const CONFIG_PATH: &str = "config.toml";
+
+pub fn load_config() -> Result<Config, Error> {
+    load_config_from(Path::new(CONFIG_PATH))
+}
+
+pub fn load_config_from(path: &Path) -> Result<Config, Error> {
+    let contents = std::fs::read_to_string(path).context(Read { path })?;
+    toml::from_str(&contents).context(Parse { path })
+}
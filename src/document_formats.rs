@@ -0,0 +1,243 @@
+//! Parsing of structured document formats (CSV, JSON, NDJSON) into the
+//! per-column `Record`s that `indexer::DocIndexer` indexes as dynamic
+//! fields, instead of as an opaque text blob.
+
+use serde_json::Value;
+use snafu::{ResultExt, Snafu};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Snafu)]
+pub enum Error {
+    #[snafu(display("could not open {}: {}", path.display(), source))]
+    Open {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+}
+
+/// One parsed record, keyed by column/field name. Values are kept as
+/// `serde_json::Value` so numeric-looking values round-trip into tantivy's
+/// numeric JSON handling instead of being flattened to strings.
+pub type Record = BTreeMap<String, Value>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DocumentFormat {
+    Csv,
+    Json,
+    Ndjson,
+}
+
+/// Detects a structured format from a file's extension, falling back to
+/// sniffing its content (for extension-less files or mislabeled ones, e.g.
+/// a `.txt` that's actually JSON). Returns `None` for anything that should
+/// fall back to whole-file text indexing.
+pub fn detect_format(path: &Path) -> Option<DocumentFormat> {
+    detect_format_from_extension(path).or_else(|| sniff_format(path))
+}
+
+fn detect_format_from_extension(path: &Path) -> Option<DocumentFormat> {
+    match path.extension().and_then(|ext| ext.to_str())?.to_lowercase().as_str() {
+        "csv" => Some(DocumentFormat::Csv),
+        "json" => Some(DocumentFormat::Json),
+        "ndjson" | "jsonl" => Some(DocumentFormat::Ndjson),
+        _ => None,
+    }
+}
+
+const SNIFF_BYTES: usize = 512;
+
+/// Peeks at the start of `path` and guesses a format from its shape: a
+/// top-level `{`/`[` is JSON, unless multiple lines each start their own
+/// `{...}` object, in which case it's NDJSON; a first line containing commas
+/// is treated as CSV. Anything else (including an unreadable file) yields
+/// `None`, falling back to whole-file text indexing.
+fn sniff_format(path: &Path) -> Option<DocumentFormat> {
+    use std::io::Read;
+
+    let mut file = std::fs::File::open(path).ok()?;
+    let mut buf = [0u8; SNIFF_BYTES];
+    let n = file.read(&mut buf).ok()?;
+    let sample = std::str::from_utf8(&buf[..n]).ok()?.trim_start();
+
+    if sample.starts_with('{') || sample.starts_with('[') {
+        let lines = sample.lines().filter(|line| !line.trim().is_empty());
+        let looks_like_ndjson = lines.clone().count() > 1
+            && lines.clone().all(|line| line.trim_start().starts_with('{'));
+
+        return Some(if looks_like_ndjson {
+            DocumentFormat::Ndjson
+        } else {
+            DocumentFormat::Json
+        });
+    }
+
+    let first_line = sample.lines().next()?;
+    if first_line.contains(',') {
+        return Some(DocumentFormat::Csv);
+    }
+
+    None
+}
+
+/// Parses `path` according to `format`, skipping malformed rows/lines rather
+/// than failing the whole file.
+pub fn parse_file(path: &Path, format: DocumentFormat) -> Result<Vec<Record>, Error> {
+    let contents = std::fs::read_to_string(path).context(Open { path })?;
+
+    Ok(match format {
+        DocumentFormat::Csv => parse_csv(&contents),
+        DocumentFormat::Json => parse_json(&contents),
+        DocumentFormat::Ndjson => parse_ndjson(&contents),
+    })
+}
+
+fn value_from_csv_field(field: &str) -> Value {
+    if let Ok(n) = field.parse::<i64>() {
+        Value::from(n)
+    } else if let Ok(n) = field.parse::<f64>() {
+        Value::from(n)
+    } else {
+        Value::from(field)
+    }
+}
+
+fn parse_csv(contents: &str) -> Vec<Record> {
+    let mut reader = csv::ReaderBuilder::new()
+        .flexible(true)
+        .from_reader(contents.as_bytes());
+
+    let headers = match reader.headers() {
+        Ok(headers) => headers.clone(),
+        Err(_) => return Vec::new(),
+    };
+
+    reader
+        .records()
+        .filter_map(|row| row.ok())
+        .map(|row| {
+            headers
+                .iter()
+                .zip(row.iter())
+                .map(|(col, field)| (col.to_string(), value_from_csv_field(field)))
+                .collect()
+        })
+        .collect()
+}
+
+fn object_to_record(value: Value) -> Option<Record> {
+    match value {
+        Value::Object(map) => Some(map.into_iter().collect()),
+        _ => None,
+    }
+}
+
+fn parse_json(contents: &str) -> Vec<Record> {
+    let value: Value = match serde_json::from_str(contents) {
+        Ok(value) => value,
+        Err(_) => return Vec::new(),
+    };
+
+    match value {
+        Value::Array(values) => values.into_iter().filter_map(object_to_record).collect(),
+        Value::Object(_) => object_to_record(value).into_iter().collect(),
+        _ => Vec::new(),
+    }
+}
+
+fn parse_ndjson(contents: &str) -> Vec<Record> {
+    contents
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .filter_map(object_to_record)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn csv_field_coerces_integers_and_floats_but_not_text() {
+        assert_eq!(value_from_csv_field("42"), Value::from(42_i64));
+        assert_eq!(value_from_csv_field("3.14"), Value::from(3.14_f64));
+        assert_eq!(value_from_csv_field("abc"), Value::from("abc"));
+    }
+
+    #[test]
+    fn csv_rows_with_fewer_fields_than_headers_are_missing_those_columns() {
+        let records = parse_csv("a,b,c\n1,2\n");
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].get("a"), Some(&Value::from(1_i64)));
+        assert_eq!(records[0].get("b"), Some(&Value::from(2_i64)));
+        assert_eq!(records[0].get("c"), None);
+    }
+
+    #[test]
+    fn json_top_level_array_becomes_one_record_per_object() {
+        let records = parse_json(r#"[{"a": 1}, {"b": "x"}]"#);
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].get("a"), Some(&Value::from(1_i64)));
+        assert_eq!(records[1].get("b"), Some(&Value::from("x")));
+    }
+
+    #[test]
+    fn json_single_top_level_object_becomes_one_record() {
+        let records = parse_json(r#"{"a": 1, "b": 2}"#);
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].get("a"), Some(&Value::from(1_i64)));
+    }
+
+    #[test]
+    fn json_top_level_scalar_is_skipped_rather_than_erroring() {
+        assert_eq!(parse_json("42"), Vec::<Record>::new());
+        assert_eq!(parse_json("not json"), Vec::<Record>::new());
+    }
+
+    #[test]
+    fn ndjson_skips_malformed_and_blank_lines() {
+        let records = parse_ndjson("{\"a\": 1}\n\nnot json\n{\"a\": 2}\n");
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].get("a"), Some(&Value::from(1_i64)));
+        assert_eq!(records[1].get("a"), Some(&Value::from(2_i64)));
+    }
+
+    fn write_temp(name: &str, contents: &str) -> std::path::PathBuf {
+        let path = std::env::temp_dir().join(format!(
+            "document_formats_test_{}_{}",
+            std::process::id(),
+            name
+        ));
+        std::fs::write(&path, contents).unwrap();
+        path
+    }
+
+    #[test]
+    fn sniffs_json_object_without_a_recognised_extension() {
+        let path = write_temp("object.txt", r#"{"a": 1, "b": 2}"#);
+        assert_eq!(detect_format(&path), Some(DocumentFormat::Json));
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn sniffs_ndjson_without_a_recognised_extension() {
+        let path = write_temp("lines.txt", "{\"a\": 1}\n{\"a\": 2}\n{\"a\": 3}\n");
+        assert_eq!(detect_format(&path), Some(DocumentFormat::Ndjson));
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn sniffs_csv_without_a_recognised_extension() {
+        let path = write_temp("table.dat", "a,b,c\n1,2,3\n");
+        assert_eq!(detect_format(&path), Some(DocumentFormat::Csv));
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn plain_prose_without_a_recognised_extension_is_not_detected() {
+        let path = write_temp("prose.txt", "just some plain text with no structure here\n");
+        assert_eq!(detect_format(&path), None);
+        std::fs::remove_file(&path).unwrap();
+    }
+}
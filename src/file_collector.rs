@@ -0,0 +1,86 @@
+use crate::config::Config;
+use crate::last_modified_cache::LastModifiedCache;
+use snafu::{ResultExt, Snafu};
+use std::path::PathBuf;
+
+#[derive(Debug, Snafu)]
+pub enum Error {
+    #[snafu(display("could not walk {}: {}", path.display(), source))]
+    Walk {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+}
+
+impl Error {
+    /// The path that was being collected when this error occurred, so
+    /// callers can record it as a failed indexing task.
+    pub fn path(&self) -> &PathBuf {
+        match self {
+            Error::Walk { path, .. } => path,
+        }
+    }
+}
+
+/// Iterates over every file under the configured watch directories that has
+/// changed (or is new) since the last run, according to the
+/// `LastModifiedCache`.
+pub struct FilesCollectorIteror {
+    pending: Vec<PathBuf>,
+    cache: LastModifiedCache,
+}
+
+impl Iterator for FilesCollectorIteror {
+    type Item = Result<PathBuf, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some(path) = self.pending.pop() {
+            match std::fs::metadata(&path) {
+                Ok(meta) => {
+                    let modified = meta.modified().unwrap_or(std::time::SystemTime::UNIX_EPOCH);
+                    if self.cache.is_unchanged(&path, modified) {
+                        continue;
+                    }
+                    self.cache.record(path.clone(), modified);
+                    return Some(Ok(path));
+                }
+                Err(source) => return Some(Err(Error::Walk { path, source })),
+            }
+        }
+
+        // Every pending path has been visited: persist the cache so the next
+        // run skips the files recorded here.
+        if let Err(e) = self.cache.save() {
+            eprintln!("failed to save last-modified cache: {}", e);
+        }
+
+        None
+    }
+}
+
+fn walk(dir: &PathBuf, out: &mut Vec<PathBuf>) -> Result<(), Error> {
+    for entry in std::fs::read_dir(dir).context(Walk { path: dir.clone() })? {
+        let entry = entry.context(Walk { path: dir.clone() })?;
+        let path = entry.path();
+        if path.is_dir() {
+            walk(&path, out)?;
+        } else {
+            out.push(path);
+        }
+    }
+
+    Ok(())
+}
+
+pub fn collect_files(
+    config: &Config,
+    cache: LastModifiedCache,
+) -> Result<FilesCollectorIteror, Error> {
+    let mut pending = Vec::new();
+
+    for dir in &config.watch_dirs {
+        walk(dir, &mut pending)?;
+    }
+
+    Ok(FilesCollectorIteror { pending, cache })
+}
@@ -0,0 +1,92 @@
+use crate::searcher::Searcher;
+use crate::tasks::TaskStore;
+
+/// Launches the desktop search GUI, blocking the calling thread until the
+/// window is closed.
+pub fn spawn(searcher: Searcher, tasks: TaskStore) {
+    let app = SearchApp::new(searcher, tasks);
+    let options = eframe::NativeOptions::default();
+    eframe::run_native(
+        "some-indexed-document-searcher",
+        options,
+        Box::new(|_cc| Box::new(app)),
+    )
+    .expect("gui exited with an error");
+}
+
+const STATUS_FILTERS: &[&str] = &["all", "enqueued", "processing", "succeeded", "failed"];
+
+struct SearchApp {
+    searcher: Searcher,
+    tasks: TaskStore,
+    query: String,
+    status_filter: &'static str,
+}
+
+impl SearchApp {
+    fn new(searcher: Searcher, tasks: TaskStore) -> Self {
+        SearchApp {
+            searcher,
+            tasks,
+            query: String::new(),
+            status_filter: "all",
+        }
+    }
+}
+
+impl eframe::App for SearchApp {
+    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        egui::SidePanel::right("tasks").show(ctx, |ui| {
+            ui.heading("indexing tasks");
+
+            egui::ComboBox::from_label("status")
+                .selected_text(self.status_filter)
+                .show_ui(ui, |ui| {
+                    for status in STATUS_FILTERS {
+                        ui.selectable_value(&mut self.status_filter, status, *status);
+                    }
+                });
+
+            let tasks = if self.status_filter == "all" {
+                self.tasks.tasks()
+            } else {
+                self.tasks.tasks_with_status(self.status_filter)
+            };
+
+            egui::ScrollArea::vertical().show(ui, |ui| {
+                for task in tasks {
+                    let detail = match &task.status {
+                        crate::tasks::TaskStatus::Failed { error } => {
+                            format!("#{} {} [{}] {}", task.id, task.path.display(), task.status.label(), error)
+                        }
+                        _ => format!("#{} {} [{}]", task.id, task.path.display(), task.status.label()),
+                    };
+                    ui.label(detail);
+                }
+            });
+        });
+
+        egui::CentralPanel::default().show(ctx, |ui| {
+            ui.heading("some-indexed-document-searcher");
+            ui.label(format!(
+                "indexed: {} / {} tasks",
+                self.tasks.count_with_status("succeeded"),
+                self.tasks.len()
+            ));
+            ui.text_edit_singleline(&mut self.query);
+
+            if !self.query.is_empty() {
+                match self.searcher.search(&self.query, 20, 0) {
+                    Ok(hits) => {
+                        for hit in hits {
+                            ui.label(format!("{:?}", hit.doc));
+                        }
+                    }
+                    Err(e) => {
+                        ui.colored_label(egui::Color32::RED, format!("search error: {}", e));
+                    }
+                }
+            }
+        });
+    }
+}
@@ -0,0 +1,275 @@
+use crate::config::Config;
+use crate::document_formats::{self, Record};
+use crate::once_every;
+use crate::tasks::{TaskId, TaskStore};
+use snafu::{ResultExt, Snafu};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tantivy::schema::{Schema, FAST, STORED, STRING, TEXT};
+use tantivy::{doc, Index, IndexWriter, TantivyError};
+
+/// How often the background committer flushes the writer while the workers
+/// are running, so the `Searcher`'s reader picks up freshly-indexed
+/// documents instead of only seeing them once `close()` runs.
+const COMMIT_INTERVAL: Duration = Duration::from_secs(2);
+
+#[derive(Debug, Snafu)]
+pub enum Error {
+    #[snafu(display("could not open/create index at {}: {}", path.display(), source))]
+    OpenIndex {
+        path: PathBuf,
+        source: TantivyError,
+    },
+    #[snafu(display("could not create index writer: {}", source))]
+    CreateWriter { source: TantivyError },
+    #[snafu(display("could not read {}: {}", path.display(), source))]
+    ReadFile {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    #[snafu(display("could not parse structured document {}: {}", path.display(), source))]
+    ParseDocument {
+        path: PathBuf,
+        source: document_formats::Error,
+    },
+    #[snafu(display("could not commit index: {}", source))]
+    Commit { source: TantivyError },
+}
+
+/// A unit of work for the indexer: either a whole file to be indexed as one
+/// text blob, or a pre-parsed set of structured records (one per CSV row /
+/// JSON object / NDJSON line) to be indexed as individual documents with
+/// per-column fields.
+pub enum IndexRequest {
+    RawFile(PathBuf),
+    Records {
+        source_path: PathBuf,
+        records: Vec<Record>,
+    },
+}
+
+impl IndexRequest {
+    /// Builds the appropriate `IndexRequest` for `path`, detecting its
+    /// format from the extension (or by sniffing its content) and falling
+    /// back to whole-file text indexing for anything unstructured.
+    pub fn for_path(path: PathBuf) -> Result<Self, Error> {
+        match document_formats::detect_format(&path) {
+            Some(format) => {
+                let records = document_formats::parse_file(&path, format)
+                    .context(ParseDocument { path: path.clone() })?;
+                Ok(IndexRequest::Records {
+                    source_path: path,
+                    records,
+                })
+            }
+            None => Ok(IndexRequest::RawFile(path)),
+        }
+    }
+
+    pub fn source_path(&self) -> &PathBuf {
+        match self {
+            IndexRequest::RawFile(path) => path,
+            IndexRequest::Records { source_path, .. } => source_path,
+        }
+    }
+}
+
+pub struct DocIndexer {
+    index: Index,
+    schema: Schema,
+    writer: Arc<Mutex<Option<IndexWriter>>>,
+    sender: Option<crossbeam_channel::Sender<(TaskId, IndexRequest)>>,
+    receiver: crossbeam_channel::Receiver<(TaskId, IndexRequest)>,
+    tasks: TaskStore,
+    num_threads: usize,
+    workers: Vec<std::thread::JoinHandle<()>>,
+    committer_running: Arc<AtomicBool>,
+    committer: Option<std::thread::JoinHandle<()>>,
+}
+
+const SOURCE_PATH_FIELD: &str = "source_path";
+const BODY_FIELD: &str = "body";
+const FIELDS_FIELD: &str = "fields";
+
+impl DocIndexer {
+    /// Builds the tantivy schema shared by raw-text and structured
+    /// documents: a stored `source_path`, a full-text `body` for whole-file
+    /// indexing, and a dynamic JSON `fields` object holding one entry per
+    /// structured column/key. tantivy infers text vs numeric handling for
+    /// each JSON value, so range queries work on numeric columns for free.
+    fn build_schema() -> Schema {
+        let mut builder = Schema::builder();
+        builder.add_text_field(SOURCE_PATH_FIELD, STRING | STORED);
+        builder.add_text_field(BODY_FIELD, TEXT);
+        builder.add_json_field(FIELDS_FIELD, TEXT | STORED | FAST);
+        builder.build()
+    }
+
+    pub fn schema(&self) -> &Schema {
+        &self.schema
+    }
+
+    pub fn new(config: &Config) -> Result<Self, Error> {
+        let schema = Self::build_schema();
+        let index = Index::open_or_create(
+            tantivy::directory::MmapDirectory::open(&config.index_dir)
+                .context(OpenIndex { path: config.index_dir.clone() })?,
+            schema.clone(),
+        )
+        .context(OpenIndex { path: config.index_dir.clone() })?;
+
+        let (sender, receiver) = crossbeam_channel::unbounded();
+
+        Ok(DocIndexer {
+            index,
+            schema,
+            writer: Arc::new(Mutex::new(None)),
+            sender: Some(sender),
+            receiver,
+            tasks: TaskStore::new(),
+            num_threads: config.indexer_threads,
+            workers: Vec::new(),
+            committer_running: Arc::new(AtomicBool::new(false)),
+            committer: None,
+        })
+    }
+
+    pub fn indexer(&self) -> &Index {
+        &self.index
+    }
+
+    /// Opens an existing index read-only, without creating it if missing and
+    /// without spawning any writer/worker threads. Used by headless
+    /// consumers (e.g. the CLI `search` subcommand) that only ever query.
+    pub fn open_readonly(config: &Config) -> Result<(Index, Schema), Error> {
+        let schema = Self::build_schema();
+        let index = Index::open(
+            tantivy::directory::MmapDirectory::open(&config.index_dir)
+                .context(OpenIndex { path: config.index_dir.clone() })?,
+        )
+        .context(OpenIndex { path: config.index_dir.clone() })?;
+
+        Ok((index, schema))
+    }
+
+    /// Returns a handle to the task store, shared with (and mutated by) the
+    /// worker threads, so callers such as the GUI can observe live progress.
+    pub fn tasks(&self) -> TaskStore {
+        self.tasks.clone()
+    }
+
+    /// Enqueues `request` and returns the id of the task tracking it.
+    pub fn add_job(&self, request: IndexRequest) -> TaskId {
+        let id = self.tasks.enqueue(request.source_path().clone());
+        if let Some(sender) = &self.sender {
+            let _ = sender.send((id, request));
+        }
+        id
+    }
+
+    /// Records a task that failed before it could even be enqueued for
+    /// indexing (e.g. a crawl or format-parse failure), so it still shows up
+    /// in `tasks()` as `Failed` with its error, instead of being dropped
+    /// silently.
+    pub fn fail_job(&self, path: PathBuf, error: String) -> TaskId {
+        let id = self.tasks.enqueue(path);
+        self.tasks.set_failed(id, error);
+        id
+    }
+
+    pub fn spawn_workers(&mut self) -> Result<(), Error> {
+        let writer = self
+            .index
+            .writer(50_000_000)
+            .context(CreateWriter)?;
+        *self.writer.lock().unwrap() = Some(writer);
+
+        for _ in 0..self.num_threads {
+            let receiver = self.receiver.clone();
+            let writer = self.writer.clone();
+            let schema = self.schema.clone();
+            let tasks = self.tasks.clone();
+
+            self.workers.push(std::thread::spawn(move || {
+                for (id, request) in receiver {
+                    tasks.set_processing(id);
+                    match index_request(&writer, &schema, request) {
+                        Ok(()) => tasks.set_succeeded(id),
+                        Err(e) => tasks.set_failed(id, e.to_string()),
+                    }
+                }
+            }));
+        }
+
+        self.committer_running.store(true, Ordering::Relaxed);
+        let committer_writer = self.writer.clone();
+        let committer_running = self.committer_running.clone();
+        self.committer = Some(once_every::once_every(
+            COMMIT_INTERVAL,
+            committer_running,
+            move || {
+                if let Some(writer) = committer_writer.lock().unwrap().as_mut() {
+                    let _ = writer.commit();
+                }
+            },
+        ));
+
+        Ok(())
+    }
+
+    /// Signals workers to stop once the queue drains, and waits for them.
+    pub fn close(&mut self) {
+        // Dropping the last sender disconnects the channel, which ends the
+        // `for (id, request) in receiver` loop in each worker once it's
+        // drained the queue.
+        self.sender.take();
+        for worker in self.workers.drain(..) {
+            let _ = worker.join();
+        }
+
+        self.committer_running.store(false, Ordering::Relaxed);
+        if let Some(committer) = self.committer.take() {
+            let _ = committer.join();
+        }
+
+        if let Some(writer) = self.writer.lock().unwrap().as_mut() {
+            let _ = writer.commit();
+        }
+    }
+}
+
+fn index_request(
+    writer: &Mutex<Option<IndexWriter>>,
+    schema: &Schema,
+    request: IndexRequest,
+) -> Result<(), Error> {
+    let source_path_field = schema.get_field(SOURCE_PATH_FIELD).unwrap();
+    let body_field = schema.get_field(BODY_FIELD).unwrap();
+    let fields_field = schema.get_field(FIELDS_FIELD).unwrap();
+
+    let mut guard = writer.lock().unwrap();
+    let writer = guard.as_mut().expect("writer is set before workers run");
+
+    match request {
+        IndexRequest::RawFile(path) => {
+            let body = std::fs::read_to_string(&path).context(ReadFile { path: path.clone() })?;
+            writer.add_document(doc!(
+                source_path_field => path.display().to_string(),
+                body_field => body,
+            ));
+        }
+        IndexRequest::Records { source_path, records } => {
+            for record in records {
+                let fields_value = serde_json::Value::Object(record.into_iter().collect());
+                writer.add_document(doc!(
+                    source_path_field => source_path.display().to_string(),
+                    fields_field => fields_value,
+                ));
+            }
+        }
+    }
+
+    Ok(())
+}
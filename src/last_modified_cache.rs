@@ -0,0 +1,68 @@
+use crate::config::Config;
+use snafu::{ResultExt, Snafu};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+#[derive(Debug, Snafu)]
+pub enum Error {
+    #[snafu(display("could not read last-modified cache {}: {}", path.display(), source))]
+    Read {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    #[snafu(display("could not parse last-modified cache {}: {}", path.display(), source))]
+    Parse {
+        path: PathBuf,
+        source: serde_json::Error,
+    },
+    #[snafu(display("could not write last-modified cache {}: {}", path.display(), source))]
+    Write {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+}
+
+/// Tracks the last-seen modification time of every file we've indexed, so a
+/// restart only re-indexes files that actually changed.
+pub struct LastModifiedCache {
+    path: PathBuf,
+    entries: HashMap<PathBuf, SystemTime>,
+}
+
+impl LastModifiedCache {
+    pub fn new(config: &Config) -> Result<Self, Error> {
+        let path = config.cache_path.clone();
+
+        let entries = if path.exists() {
+            let contents = std::fs::read_to_string(&path).context(Read { path: &path })?;
+            serde_json::from_str(&contents).context(Parse { path: &path })?
+        } else {
+            HashMap::new()
+        };
+
+        Ok(LastModifiedCache { path, entries })
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn is_unchanged(&self, path: &Path, modified: SystemTime) -> bool {
+        self.entries.get(path) == Some(&modified)
+    }
+
+    pub fn record(&mut self, path: PathBuf, modified: SystemTime) {
+        self.entries.insert(path, modified);
+    }
+
+    pub fn save(&self) -> Result<(), Error> {
+        let contents =
+            serde_json::to_string(&self.entries).expect("last modified cache is always serializable");
+        std::fs::write(&self.path, contents).context(Write { path: &self.path })
+    }
+}
@@ -1,18 +1,28 @@
+use clap::Parser;
 use ctrlc;
 use snafu::{ErrorCompat, ResultExt, Snafu};
 use std::sync::{
-    atomic::{AtomicBool, AtomicUsize, Ordering},
+    atomic::{AtomicBool, Ordering},
     Arc,
 };
+use std::time::Duration;
 
+mod cli;
 mod config;
+mod document_formats;
 mod file_collector;
 mod indexer;
 mod searcher;
 mod last_modified_cache;
 mod once_every;
+mod server;
+mod snapshot;
+mod tasks;
 mod gui;
 
+use cli::Command;
+use config::Config;
+
 #[derive(Debug, Snafu)]
 enum SIDSError {
     #[snafu]
@@ -23,21 +33,35 @@ enum SIDSError {
     IndexerError { source: indexer::Error },
     #[snafu]
     LastModifiedCacheError { source: last_modified_cache::Error },
+    #[snafu]
+    SnapshotError { source: snapshot::Error },
+    #[snafu]
+    ServerError { source: server::Error },
 }
 
 struct IndexerData {
     file_collector: file_collector::FilesCollectorIteror,
     doc_indexer: indexer::DocIndexer,
-    indexed_files: Arc<AtomicUsize>,
     running: Arc<AtomicBool>,
 }
 
 fn deploy_indexer(mut data: IndexerData) -> std::thread::JoinHandle<()> {
     std::thread::spawn(move || {
         for file in data.file_collector {
-            if let Ok(file) = file {
-                data.doc_indexer.add_job(indexer::IndexRequest(file));
-                data.indexed_files.fetch_add(1, Ordering::Relaxed);
+            match file {
+                Ok(path) => match indexer::IndexRequest::for_path(path.clone()) {
+                    Ok(request) => {
+                        data.doc_indexer.add_job(request);
+                    }
+                    Err(e) => {
+                        eprintln!("failed to prepare file for indexing: {}", e);
+                        data.doc_indexer.fail_job(path, e.to_string());
+                    }
+                },
+                Err(e) => {
+                    eprintln!("failed to collect file: {}", e);
+                    data.doc_indexer.fail_job(e.path().clone(), e.to_string());
+                }
             }
 
             if !data.running.load(Ordering::Relaxed) {
@@ -49,6 +73,34 @@ fn deploy_indexer(mut data: IndexerData) -> std::thread::JoinHandle<()> {
     })
 }
 
+fn deploy_snapshot_thread(
+    config: Config,
+    snapshot_path: std::path::PathBuf,
+    running: Arc<AtomicBool>,
+) -> std::thread::JoinHandle<()> {
+    once_every::once_every(
+        Duration::from_secs(config.snapshot_interval_secs),
+        running,
+        move || {
+            if let Err(e) = snapshot::create_snapshot(&config, &snapshot_path) {
+                eprintln!("failed to write snapshot: {}", e);
+            }
+        },
+    )
+}
+
+fn deploy_server_thread(
+    searcher: searcher::Searcher,
+    bind_addr: String,
+    running: Arc<AtomicBool>,
+) -> std::thread::JoinHandle<()> {
+    std::thread::spawn(move || {
+        if let Err(e) = server::serve(searcher, &bind_addr, running) {
+            eprintln!("HTTP server failed: {}", e);
+        }
+    })
+}
+
 fn deploy_cc_handler() -> Arc<AtomicBool> {
     let running = Arc::new(AtomicBool::new(true));
     let r = running.clone();
@@ -61,45 +113,151 @@ fn deploy_cc_handler() -> Arc<AtomicBool> {
     running
 }
 
-fn main_inner() -> Result<(), SIDSError> {
-    let config = config::load_config().context(ConfigLoad)?;
+fn restore_snapshot_if_present(config: &Config) -> Result<(), SIDSError> {
+    if let Some(snapshot_path) = &config.snapshot_path {
+        if snapshot_path.exists() {
+            snapshot::restore_snapshot(config, snapshot_path).context(SnapshotError)?;
+        }
+    }
 
-    println!("config: {:#?}", config);
+    Ok(())
+}
+
+/// Opens (creating if necessary) the index and spawns its worker threads.
+/// Shared by the `gui` and `index` subcommands, which both crawl and write.
+fn build_indexer(config: &Config) -> Result<indexer::DocIndexer, SIDSError> {
+    let mut doc_indexer = indexer::DocIndexer::new(config).context(IndexerError)?;
+    doc_indexer.spawn_workers().context(IndexerError)?;
+    Ok(doc_indexer)
+}
 
+/// Crawls `config`'s watch directories and feeds every changed file into
+/// `doc_indexer`, returning a handle that can be joined once the crawl (and
+/// any in-flight indexing) finishes.
+fn deploy_crawl(
+    config: &Config,
+    doc_indexer: indexer::DocIndexer,
+    running: Arc<AtomicBool>,
+) -> Result<std::thread::JoinHandle<()>, SIDSError> {
     let modified_cache =
-        last_modified_cache::LastModifiedCache::new(&config).context(LastModifiedCacheError)?;
+        last_modified_cache::LastModifiedCache::new(config).context(LastModifiedCacheError)?;
 
-    let mut doc_indexer = indexer::DocIndexer::new(&config).context(IndexerError)?;
-    doc_indexer.spawn_workers().context(IndexerError)?;
+    let indexer_data = IndexerData {
+        file_collector: file_collector::collect_files(config, modified_cache)
+            .context(CollectorError)?,
+        doc_indexer,
+        running,
+    };
 
-    let indexer = doc_indexer.indexer().clone();
-    let schema = doc_indexer.schema().clone();
+    Ok(deploy_indexer(indexer_data))
+}
 
-    let searcher = searcher::Searcher::new(schema, indexer).unwrap();
+fn run_gui(config: Config, serve: bool, bind: Option<String>) -> Result<(), SIDSError> {
+    restore_snapshot_if_present(&config)?;
+
+    let doc_indexer = build_indexer(&config)?;
+
+    let index = doc_indexer.indexer().clone();
+    let schema = doc_indexer.schema().clone();
+    let searcher = searcher::Searcher::new(schema, index).unwrap();
+    let tasks = doc_indexer.tasks();
 
     let running = deploy_cc_handler();
 
-    let indexed_files = Arc::new(AtomicUsize::new(modified_cache.len()));
+    let indexer_thread = deploy_crawl(&config, doc_indexer, running.clone())?;
 
-    let indexer_data = IndexerData {
-        file_collector: file_collector::collect_files(&config, modified_cache).context(CollectorError)?,
-        doc_indexer,
-        indexed_files: indexed_files.clone(),
-        running: running.clone(),
-    };
+    let snapshot_thread = config.snapshot_path.clone().map(|snapshot_path| {
+        deploy_snapshot_thread(config.clone(), snapshot_path, running.clone())
+    });
 
-    let indexer_thread = deploy_indexer(indexer_data);
+    let server_thread = serve.then(|| {
+        let bind_addr = bind.unwrap_or_else(|| config.serve_bind_addr.clone());
+        deploy_server_thread(searcher.clone(), bind_addr, running.clone())
+    });
 
-    gui::spawn(searcher, indexed_files);
+    gui::spawn(searcher, tasks);
 
     // set running to false when the gui quits
     running.store(false, Ordering::Relaxed);
 
     let _ = indexer_thread.join();
+    if let Some(snapshot_thread) = snapshot_thread {
+        let _ = snapshot_thread.join();
+    }
+    if let Some(server_thread) = server_thread {
+        let _ = server_thread.join();
+    }
 
     Ok(())
 }
 
+/// Headless equivalent of `run_gui`: crawls and indexes the configured
+/// directories, then exits once the crawl (and any in-flight indexing) is
+/// done, without ever constructing the GUI. Useful from cron/CI.
+fn run_index(config: Config) -> Result<(), SIDSError> {
+    restore_snapshot_if_present(&config)?;
+
+    let doc_indexer = build_indexer(&config)?;
+    let running = deploy_cc_handler();
+
+    let indexer_thread = deploy_crawl(&config, doc_indexer, running)?;
+    let _ = indexer_thread.join();
+
+    Ok(())
+}
+
+/// Opens the existing index read-only and prints ranked hits for `query` to
+/// stdout. Never deploys the indexer thread or a writer.
+fn run_search(config: Config, query: &str, limit: usize, offset: usize) -> Result<(), SIDSError> {
+    let (index, schema) = indexer::DocIndexer::open_readonly(&config).context(IndexerError)?;
+    let searcher = searcher::Searcher::new(schema, index).unwrap();
+
+    match searcher.search(query, limit, offset) {
+        Ok(hits) => {
+            for hit in hits {
+                println!(
+                    "{:.4}\t{}",
+                    hit.score,
+                    serde_json::to_string(&hit.doc).expect("tantivy documents are serializable")
+                );
+            }
+        }
+        Err(e) => eprintln!("search failed: {}", e),
+    }
+
+    Ok(())
+}
+
+/// Opens the existing index read-only and serves it over HTTP until the
+/// process receives Ctrl-C, without ever constructing the GUI.
+fn run_serve(config: Config, bind: Option<String>) -> Result<(), SIDSError> {
+    let (index, schema) = indexer::DocIndexer::open_readonly(&config).context(IndexerError)?;
+    let searcher = searcher::Searcher::new(schema, index).unwrap();
+    let bind_addr = bind.unwrap_or_else(|| config.serve_bind_addr.clone());
+
+    let running = deploy_cc_handler();
+
+    server::serve(searcher, &bind_addr, running).context(ServerError)
+}
+
+fn main_inner() -> Result<(), SIDSError> {
+    let cli = cli::MainCommand::parse();
+
+    tracing_subscriber::fmt()
+        .with_env_filter(tracing_subscriber::EnvFilter::new(&cli.log_level))
+        .init();
+
+    let config = config::load_config().context(ConfigLoad)?;
+    tracing::debug!("config: {:#?}", config);
+
+    match cli.command() {
+        Command::Gui { serve, bind } => run_gui(config, serve, bind),
+        Command::Index => run_index(config),
+        Command::Search { query, limit, offset } => run_search(config, &query, limit, offset),
+        Command::Serve { bind } => run_serve(config, bind),
+    }
+}
+
 fn main() {
     if let Err(e) = main_inner() {
         eprintln!("Oops: {}", e);
@@ -0,0 +1,29 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// How long to sleep between `running` checks while waiting out an
+/// interval, so shutdown is noticed quickly even when `interval` is long.
+const POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+/// Spawns a background thread that calls `f` repeatedly, waiting `interval`
+/// between calls, until `running` is set to `false`. The wait is done in
+/// short increments so shutdown isn't stalled until the full interval
+/// elapses.
+pub fn once_every<F>(interval: Duration, running: Arc<AtomicBool>, mut f: F) -> std::thread::JoinHandle<()>
+where
+    F: FnMut() + Send + 'static,
+{
+    std::thread::spawn(move || {
+        while running.load(Ordering::Relaxed) {
+            f();
+
+            let mut waited = Duration::ZERO;
+            while waited < interval && running.load(Ordering::Relaxed) {
+                let step = POLL_INTERVAL.min(interval - waited);
+                std::thread::sleep(step);
+                waited += step;
+            }
+        }
+    })
+}
@@ -0,0 +1,69 @@
+use snafu::{ResultExt, Snafu};
+use tantivy::collector::TopDocs;
+use tantivy::query::QueryParser;
+use tantivy::{Index, Score, TantivyError};
+
+#[derive(Debug, Snafu)]
+pub enum Error {
+    #[snafu(display("could not build reader: {}", source))]
+    Reader { source: TantivyError },
+    #[snafu(display("could not parse query {:?}: {}", query, source))]
+    QueryParse {
+        query: String,
+        source: tantivy::query::QueryParserError,
+    },
+    #[snafu(display("search failed: {}", source))]
+    Search { source: TantivyError },
+}
+
+/// Runs queries against a tantivy `Index`, shared between the GUI and any
+/// headless consumer (e.g. the CLI `search` subcommand or the HTTP server).
+#[derive(Clone)]
+pub struct Searcher {
+    index: Index,
+    schema: tantivy::schema::Schema,
+    reader: tantivy::IndexReader,
+}
+
+#[derive(serde::Serialize)]
+pub struct Hit {
+    pub score: Score,
+    pub doc: tantivy::schema::NamedFieldDocument,
+}
+
+impl Searcher {
+    pub fn new(schema: tantivy::schema::Schema, index: Index) -> Result<Self, Error> {
+        let reader = index.reader().context(Reader)?;
+
+        Ok(Searcher {
+            index,
+            schema,
+            reader,
+        })
+    }
+
+    pub fn search(&self, query: &str, limit: usize, offset: usize) -> Result<Vec<Hit>, Error> {
+        let searcher = self.reader.searcher();
+        let fields = self.schema.fields().map(|(f, _)| f).collect::<Vec<_>>();
+        let query_parser = QueryParser::for_index(&self.index, fields);
+        let parsed = query_parser.parse_query(query).context(QueryParse { query })?;
+
+        let fetch_limit = limit.max(1).saturating_add(offset);
+        let top_docs = searcher
+            .search(&parsed, &TopDocs::with_limit(fetch_limit))
+            .context(Search)?;
+
+        Ok(top_docs
+            .into_iter()
+            .skip(offset)
+            .take(limit)
+            .map(|(score, addr)| {
+                let doc = searcher.doc(addr).expect("doc address from search is valid");
+                Hit {
+                    score,
+                    doc: self.schema.to_named_doc(&doc),
+                }
+            })
+            .collect())
+    }
+}
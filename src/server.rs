@@ -0,0 +1,116 @@
+//! Exposes `searcher::Searcher` over HTTP so other programs can query the
+//! index remotely (`GET /search?q=...&limit=...&offset=...`), sharing the
+//! same `Searcher` handle the GUI uses so a single running instance can
+//! serve a local GUI and remote queries concurrently. Per-field filtering
+//! is just tantivy query syntax (`fields.colname:value`) against the
+//! dynamic `fields` JSON field added for structured documents.
+
+use crate::searcher::Searcher;
+use serde::Serialize;
+use snafu::{ResultExt, Snafu};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+#[derive(Debug, Snafu)]
+pub enum Error {
+    #[snafu(display("could not bind HTTP server to {}: {}", bind_addr, source))]
+    Bind {
+        bind_addr: String,
+        source: std::io::Error,
+    },
+}
+
+const DEFAULT_LIMIT: usize = 10;
+
+#[derive(Serialize)]
+struct SearchResponse<'a> {
+    query: &'a str,
+    limit: usize,
+    offset: usize,
+    hits: Vec<crate::searcher::Hit>,
+}
+
+/// Serves search requests until `running` is set to `false`, checking it
+/// between polls so the thread can be shut down alongside the rest of the
+/// application.
+pub fn serve(searcher: Searcher, bind_addr: &str, running: Arc<AtomicBool>) -> Result<(), Error> {
+    let server = tiny_http::Server::http(bind_addr).map_err(|source| Error::Bind {
+        bind_addr: bind_addr.to_string(),
+        source: std::io::Error::new(std::io::ErrorKind::Other, source),
+    })?;
+
+    while running.load(Ordering::Relaxed) {
+        match server.recv_timeout(Duration::from_millis(200)) {
+            Ok(Some(request)) => handle_request(&searcher, request),
+            Ok(None) => continue,
+            Err(e) => eprintln!("HTTP server error: {}", e),
+        }
+    }
+
+    Ok(())
+}
+
+fn handle_request(searcher: &Searcher, request: tiny_http::Request) {
+    let url = request.url().to_string();
+    let response = match route(searcher, &url) {
+        Ok(body) => tiny_http::Response::from_string(body)
+            .with_header(tiny_http::Header::from_bytes(&b"Content-Type"[..], &b"application/json"[..]).unwrap()),
+        Err((status, body)) => {
+            tiny_http::Response::from_string(body).with_status_code(tiny_http::StatusCode(status))
+        }
+    };
+
+    if let Err(e) = request.respond(response) {
+        eprintln!("failed to respond to HTTP request: {}", e);
+    }
+}
+
+fn route(searcher: &Searcher, url: &str) -> Result<String, (u16, String)> {
+    let (path, query) = url.split_once('?').unwrap_or((url, ""));
+    if path != "/search" {
+        return Err((404, "not found".to_string()));
+    }
+
+    let params = parse_query(query);
+
+    let q = params.get("q").cloned().unwrap_or_default();
+    if q.is_empty() {
+        return Err((400, "missing required query parameter `q`".to_string()));
+    }
+
+    let limit = params
+        .get("limit")
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(DEFAULT_LIMIT);
+    let offset = params
+        .get("offset")
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(0);
+
+    let hits = searcher
+        .search(&q, limit, offset)
+        .map_err(|e| (500, e.to_string()))?;
+
+    serde_json::to_string(&SearchResponse {
+        query: &q,
+        limit,
+        offset,
+        hits,
+    })
+    .map_err(|e| (500, e.to_string()))
+}
+
+fn decode_query_component(component: &str) -> String {
+    urlencoding::decode(&component.replace('+', " "))
+        .unwrap_or_default()
+        .into_owned()
+}
+
+fn parse_query(query: &str) -> std::collections::HashMap<String, String> {
+    query
+        .split('&')
+        .filter_map(|pair| pair.split_once('='))
+        .map(|(k, v)| (decode_query_component(k), decode_query_component(v)))
+        .collect()
+}
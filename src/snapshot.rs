@@ -0,0 +1,104 @@
+//! Snapshotting the index directory and last-modified cache into a single
+//! compressed tarball, and restoring from one on startup, so a crash doesn't
+//! force a full re-crawl and the index can be backed up as one artifact.
+
+use crate::config::Config;
+use flate2::{read::GzDecoder, write::GzEncoder, Compression};
+use snafu::{ResultExt, Snafu};
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Snafu)]
+pub enum Error {
+    #[snafu(display("could not create snapshot temp file {}: {}", path.display(), source))]
+    CreateTempFile {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    #[snafu(display("could not append {} to snapshot: {}", path.display(), source))]
+    Append {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    #[snafu(display("could not finish writing snapshot: {}", source))]
+    Finish { source: std::io::Error },
+    #[snafu(display("could not rename {} into place at {}: {}", from.display(), to.display(), source))]
+    Rename {
+        from: PathBuf,
+        to: PathBuf,
+        source: std::io::Error,
+    },
+    #[snafu(display("could not open snapshot {}: {}", path.display(), source))]
+    OpenSnapshot {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    #[snafu(display("could not unpack snapshot into {}: {}", path.display(), source))]
+    Unpack {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+}
+
+const INDEX_DIR_ENTRY: &str = "index";
+const CACHE_FILE_ENTRY: &str = "last_modified_cache.json";
+
+/// Writes a compressed tarball containing the index directory and the
+/// last-modified cache to a temp path next to `snapshot_path`, then
+/// renames it into place, so a crash never leaves a half-written snapshot.
+pub fn create_snapshot(config: &Config, snapshot_path: &Path) -> Result<(), Error> {
+    let tmp_path = snapshot_path.with_extension("tmp");
+
+    {
+        let file = std::fs::File::create(&tmp_path).context(CreateTempFile { path: &tmp_path })?;
+        let encoder = GzEncoder::new(file, Compression::default());
+        let mut builder = tar::Builder::new(encoder);
+
+        builder
+            .append_dir_all(INDEX_DIR_ENTRY, &config.index_dir)
+            .context(Append { path: &config.index_dir })?;
+
+        if config.cache_path.exists() {
+            builder
+                .append_path_with_name(&config.cache_path, CACHE_FILE_ENTRY)
+                .context(Append { path: &config.cache_path })?;
+        }
+
+        builder
+            .into_inner()
+            .context(Finish)?
+            .finish()
+            .context(Finish)?;
+    }
+
+    std::fs::rename(&tmp_path, snapshot_path).context(Rename {
+        from: tmp_path,
+        to: snapshot_path.to_path_buf(),
+    })
+}
+
+/// Restores the index directory and last-modified cache from `snapshot_path`,
+/// overwriting whatever is at `config.index_dir` / `config.cache_path`.
+/// Should be called before `indexer::DocIndexer::new` opens the index.
+pub fn restore_snapshot(config: &Config, snapshot_path: &Path) -> Result<(), Error> {
+    let file = std::fs::File::open(snapshot_path).context(OpenSnapshot { path: snapshot_path })?;
+    let decoder = GzDecoder::new(file);
+    let mut archive = tar::Archive::new(decoder);
+
+    for entry in archive.entries().context(Unpack { path: snapshot_path })? {
+        let mut entry = entry.context(Unpack { path: snapshot_path })?;
+        let entry_path = entry.path().context(Unpack { path: snapshot_path })?.into_owned();
+
+        if entry_path.starts_with(INDEX_DIR_ENTRY) {
+            let relative = entry_path.strip_prefix(INDEX_DIR_ENTRY).unwrap();
+            let dest = config.index_dir.join(relative);
+            if let Some(parent) = dest.parent() {
+                std::fs::create_dir_all(parent).context(Unpack { path: snapshot_path })?;
+            }
+            entry.unpack(&dest).context(Unpack { path: snapshot_path })?;
+        } else if entry_path == Path::new(CACHE_FILE_ENTRY) {
+            entry.unpack(&config.cache_path).context(Unpack { path: snapshot_path })?;
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,133 @@
+//! A per-file indexing task store, giving visibility into indexing progress
+//! beyond a bare counter: one entry per `indexer::IndexRequest`, carrying its
+//! current status and timestamps, queryable by the GUI (or any other
+//! consumer) and filterable by status.
+
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
+
+pub type TaskId = u64;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum TaskStatus {
+    Enqueued,
+    Processing,
+    Succeeded,
+    Failed { error: String },
+}
+
+impl TaskStatus {
+    pub fn label(&self) -> &'static str {
+        match self {
+            TaskStatus::Enqueued => "enqueued",
+            TaskStatus::Processing => "processing",
+            TaskStatus::Succeeded => "succeeded",
+            TaskStatus::Failed { .. } => "failed",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Task {
+    pub id: TaskId,
+    pub path: PathBuf,
+    pub status: TaskStatus,
+    pub enqueued_at: SystemTime,
+    pub finished_at: Option<SystemTime>,
+}
+
+#[derive(Default)]
+struct Inner {
+    next_id: TaskId,
+    tasks: Vec<Task>,
+}
+
+/// A shared, append-mostly store of indexing tasks. Cheap to clone (it's an
+/// `Arc` internally) so both the indexer workers and the GUI can hold a
+/// handle to the same store.
+#[derive(Clone, Default)]
+pub struct TaskStore {
+    inner: Arc<Mutex<Inner>>,
+}
+
+impl TaskStore {
+    pub fn new() -> Self {
+        TaskStore::default()
+    }
+
+    /// Records a new task in the `Enqueued` state and returns its id.
+    pub fn enqueue(&self, path: PathBuf) -> TaskId {
+        let mut inner = self.inner.lock().unwrap();
+        let id = inner.next_id;
+        inner.next_id += 1;
+
+        inner.tasks.push(Task {
+            id,
+            path,
+            status: TaskStatus::Enqueued,
+            enqueued_at: SystemTime::now(),
+            finished_at: None,
+        });
+
+        id
+    }
+
+    fn set_status(&self, id: TaskId, status: TaskStatus, finished: bool) {
+        let mut inner = self.inner.lock().unwrap();
+        if let Some(task) = inner.tasks.iter_mut().find(|t| t.id == id) {
+            task.status = status;
+            if finished {
+                task.finished_at = Some(SystemTime::now());
+            }
+        }
+    }
+
+    pub fn set_processing(&self, id: TaskId) {
+        self.set_status(id, TaskStatus::Processing, false);
+    }
+
+    pub fn set_succeeded(&self, id: TaskId) {
+        self.set_status(id, TaskStatus::Succeeded, true);
+    }
+
+    pub fn set_failed(&self, id: TaskId, error: String) {
+        self.set_status(id, TaskStatus::Failed { error }, true);
+    }
+
+    /// Returns a snapshot of every task, most recently enqueued first.
+    pub fn tasks(&self) -> Vec<Task> {
+        let mut tasks = self.inner.lock().unwrap().tasks.clone();
+        tasks.reverse();
+        tasks
+    }
+
+    /// Returns a snapshot of tasks whose status label matches `status`
+    /// (see `TaskStatus::label`), for the GUI's status filter.
+    pub fn tasks_with_status(&self, status: &str) -> Vec<Task> {
+        self.tasks()
+            .into_iter()
+            .filter(|t| t.status.label() == status)
+            .collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.inner.lock().unwrap().tasks.len()
+    }
+
+    /// Counts tasks whose status label matches `status` (see
+    /// `TaskStatus::label`), e.g. for a "succeeded" progress counter.
+    pub fn count_with_status(&self, status: &str) -> usize {
+        self.inner
+            .lock()
+            .unwrap()
+            .tasks
+            .iter()
+            .filter(|t| t.status.label() == status)
+            .count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}